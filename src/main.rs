@@ -1,6 +1,12 @@
 use std::{
-	collections::HashMap,
-	io,
+	collections::{HashMap, HashSet},
+	fs::File,
+	io::{self, BufRead, BufReader, Cursor},
+};
+use rand::{
+	Rng,
+	SeedableRng,
+	rngs::StdRng,
 };
 use svg::{
 	Document,
@@ -15,8 +21,138 @@ enum Action {
 	Forward,
 	IncrementAngle,
 	None,
+	PitchDown,
+	PitchUp,
 	Pop,
 	Push,
+	RollLeft,
+	RollRight,
+}
+
+/// A point or direction in 3D space.
+type Vector3 = (f32, f32, f32);
+
+fn add(a: Vector3, b: Vector3) -> Vector3 {
+	(a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+fn scale(v: Vector3, factor: f32) -> Vector3 {
+	(v.0 * factor, v.1 * factor, v.2 * factor)
+}
+
+fn cross(a: Vector3, b: Vector3) -> Vector3 {
+	(a.1 * b.2 - a.2 * b.1, a.2 * b.0 - a.0 * b.2, a.0 * b.1 - a.1 * b.0)
+}
+
+fn dot(a: Vector3, b: Vector3) -> f32 {
+	a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+/// Rotates `v` by `angle` radians about the unit `axis`, via Rodrigues' rotation formula.
+fn rotate(v: Vector3, axis: Vector3, angle: f32) -> Vector3 {
+	let cos = angle.cos();
+	let sin = angle.sin();
+	let parallel = scale(axis, dot(axis, v) * (1.0 - cos));
+	add(add(scale(v, cos), scale(cross(axis, v), sin)), parallel)
+}
+
+/// The turtle's 3D orientation: the direction it moves in (which roll rotates about), plus the
+/// "up" and "left" axes that yaw and pitch rotate about. Kept orthonormal by construction.
+#[derive(Clone, Copy)]
+struct Frame {
+	heading: Vector3,
+	left: Vector3,
+	up: Vector3,
+}
+
+impl Frame {
+	fn identity() -> Self {
+		Self {
+			heading: (1.0, 0.0, 0.0),
+			left: (0.0, 1.0, 0.0),
+			up: (0.0, 0.0, 1.0),
+		}
+	}
+
+	/// Turns left (positive) or right about the up axis.
+	fn yaw(&mut self, angle: f32) {
+		self.heading = rotate(self.heading, self.up, angle);
+		self.left = rotate(self.left, self.up, angle);
+	}
+
+	/// Tilts up (positive) or down about the left axis.
+	fn pitch(&mut self, angle: f32) {
+		self.heading = rotate(self.heading, self.left, -angle);
+		self.up = rotate(self.up, self.left, -angle);
+	}
+
+	/// Rolls about the heading axis.
+	fn roll(&mut self, angle: f32) {
+		self.up = rotate(self.up, self.heading, angle);
+		self.left = rotate(self.left, self.heading, angle);
+	}
+}
+
+/// A move the turtle made while walking a generated string, in order. A `LineTo` carries the
+/// bracket depth it was drawn at, so the SVG writer can vary color and width by depth.
+enum TurtleEvent {
+	MoveTo(Vector3),
+	LineTo(Vector3, u32),
+}
+
+/// An RGB color.
+type Color = (u8, u8, u8);
+
+/// How a branch's color and stroke width depend on its bracket depth: a gradient from `trunk_*`
+/// near depth 0 to `leaf_*` at the tips, approached asymptotically so it works regardless of how
+/// deep a given system's branches actually nest.
+struct StrokeStyle {
+	trunk_color: Color,
+	leaf_color: Color,
+	trunk_width: f32,
+	leaf_width: f32,
+}
+
+impl StrokeStyle {
+	/// A brown-trunk-to-green-leaf ramp, the default for presets and for custom definitions that
+	/// don't specify one.
+	fn default_plant() -> Self {
+		Self {
+			trunk_color: (101, 67, 33),
+			leaf_color: (34, 139, 34),
+			trunk_width: 5.0,
+			leaf_width: 1.0,
+		}
+	}
+
+	/// How far toward the leaf end of the ramp `depth` has gotten: 0.0 at the trunk, approaching
+	/// 1.0 as depth grows.
+	fn progress(depth: u32) -> f32 {
+		1.0 - 0.8f32.powi(depth as i32)
+	}
+
+	fn color_at(&self, depth: u32) -> Color {
+		let t = Self::progress(depth);
+		let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+		(
+			lerp(self.trunk_color.0, self.leaf_color.0),
+			lerp(self.trunk_color.1, self.leaf_color.1),
+			lerp(self.trunk_color.2, self.leaf_color.2),
+		)
+	}
+
+	fn width_at(&self, depth: u32) -> f32 {
+		let t = Self::progress(depth);
+		self.trunk_width + (self.leaf_width - self.trunk_width) * t
+	}
+}
+
+/// The file format written to stdout.
+enum OutputFormat {
+	/// A 2D projection, selected by default. Cannot represent the third dimension.
+	Svg,
+	/// A Wavefront .obj: vertices plus line elements, preserving the full 3D path.
+	Obj,
 }
 
 #[derive(Clone, Copy)]
@@ -26,11 +162,28 @@ enum LindenmeyerSystemPreset {
 	Tree,
 }
 
+/// What the user picked at the prompt: a built-in preset, or a custom grammar to parse.
+enum Selection {
+	Preset(LindenmeyerSystemPreset),
+	Custom,
+}
+
+/// A custom L-system as parsed from a definition, before being turned into a `LindenmeyerSystem`.
+struct CustomDefinition {
+	actions: HashMap<char, Action>,
+	angle_delta: f32,
+	axiom: String,
+	generations: Option<u8>,
+	rules: HashMap<char, Vec<(String, f32)>>,
+	stroke: StrokeStyle,
+}
+
 struct LindenmeyerSystem {
 	actions: HashMap<char, Action>,
 	angle_delta: f32,
 	axiom: String,
-	rules: HashMap<char, String>,
+	rules: HashMap<char, Vec<(String, f32)>>,
+	stroke: StrokeStyle,
 }
 
 impl LindenmeyerSystem {
@@ -56,10 +209,10 @@ impl LindenmeyerSystem {
 				actions.insert('-', Action::DecrementAngle);
 				angle_delta = std::f32::consts::PI / 2.0;
 				axiom = String::from("F");
-				rules.insert('F', String::from("F+G"));
-				rules.insert('G', String::from("F-G"));
-				rules.insert('+', String::from("+"));
-				rules.insert('-', String::from("-"));
+				rules.insert('F', vec![(String::from("F+G"), 1.0)]);
+				rules.insert('G', vec![(String::from("F-G"), 1.0)]);
+				rules.insert('+', vec![(String::from("+"), 1.0)]);
+				rules.insert('-', vec![(String::from("-"), 1.0)]);
 			},
 			Plant => {
 				actions.insert('X', Action::None);
@@ -70,12 +223,15 @@ impl LindenmeyerSystem {
 				actions.insert(']', Action::Pop);
 				angle_delta = (5.0 * std::f32::consts::PI) / 36.0; // 25 degrees
 				axiom = String::from("X");
-				rules.insert('X', String::from("F+[[X]-X]-F[-FX]+X"));
-				rules.insert('F', String::from("FF"));
-				rules.insert('+', String::from("+"));
-				rules.insert('-', String::from("-"));
-				rules.insert('[', String::from("["));
-				rules.insert(']', String::from("]"));
+				rules.insert('X', vec![
+					(String::from("F+[[X]-X]-F[-FX]+X"), 0.7),
+					(String::from("F-[[X]+X]+F[+FX]-X"), 0.3),
+				]);
+				rules.insert('F', vec![(String::from("FF"), 1.0)]);
+				rules.insert('+', vec![(String::from("+"), 1.0)]);
+				rules.insert('-', vec![(String::from("-"), 1.0)]);
+				rules.insert('[', vec![(String::from("["), 1.0)]);
+				rules.insert(']', vec![(String::from("]"), 1.0)]);
 			},
 			Tree => {
 				actions.insert('0', Action::Forward);
@@ -86,101 +242,552 @@ impl LindenmeyerSystem {
 				actions.insert(']', Action::Pop);
 				angle_delta = std::f32::consts::PI / 6.0;
 				axiom = String::from("0");
-				rules.insert('0', String::from("1[l0]r0"));
-				rules.insert('1', String::from("11"));
-				rules.insert('l', String::from("l"));
-				rules.insert('r', String::from("r"));
-				rules.insert('[', String::from("["));
-				rules.insert(']', String::from("]"));
+				rules.insert('0', vec![(String::from("1[l0]r0"), 1.0)]);
+				rules.insert('1', vec![(String::from("11"), 1.0)]);
+				rules.insert('l', vec![(String::from("l"), 1.0)]);
+				rules.insert('r', vec![(String::from("r"), 1.0)]);
+				rules.insert('[', vec![(String::from("["), 1.0)]);
+				rules.insert(']', vec![(String::from("]"), 1.0)]);
 			},
 		}
+		normalize_weights(&mut rules);
 		Self {
 			actions,
 			angle_delta,
 			axiom,
 			rules,
+			stroke: StrokeStyle::default_plant(),
+		}
+	}
+
+	/// Builds a system from a parsed custom definition, bypassing the hard-coded presets.
+	fn from_custom(definition: CustomDefinition) -> Self {
+		Self {
+			actions: definition.actions,
+			angle_delta: definition.angle_delta,
+			axiom: definition.axiom,
+			rules: definition.rules,
+			stroke: definition.stroke,
+		}
+	}
+
+	/// Synthesizes a random grammar: a random axiom and random, validated productions for a couple
+	/// of variables drawn from the alphabet `F X Y + - [ ]`. See `random_production` for what
+	/// makes a production valid.
+	fn from_random(rng: &mut impl Rng) -> Self {
+		const VARIABLES: [char; 2] = ['X', 'Y'];
+
+		let mut actions = HashMap::new();
+		let mut rules: HashMap<char, Vec<(String, f32)>> = HashMap::new();
+
+		actions.insert('F', Action::Forward);
+		actions.insert('+', Action::IncrementAngle);
+		actions.insert('-', Action::DecrementAngle);
+		actions.insert('[', Action::Push);
+		actions.insert(']', Action::Pop);
+		rules.insert('F', vec![(String::from("F"), 1.0)]);
+		rules.insert('+', vec![(String::from("+"), 1.0)]);
+		rules.insert('-', vec![(String::from("-"), 1.0)]);
+		rules.insert('[', vec![(String::from("["), 1.0)]);
+		rules.insert(']', vec![(String::from("]"), 1.0)]);
+
+		for variable in VARIABLES {
+			actions.insert(variable, Action::None);
+			rules.insert(variable, vec![(random_production(rng), 1.0)]);
+		}
+
+		let axiom = VARIABLES[rng.gen_range(0..VARIABLES.len())].to_string();
+
+		normalize_weights(&mut rules);
+
+		Self {
+			actions,
+			angle_delta: (5.0 * std::f32::consts::PI) / 36.0, // 25 degrees, same as Plant
+			axiom,
+			rules,
+			stroke: StrokeStyle::default_plant(),
 		}
 	}
 }
 
+/// How many generations a random grammar is grown for. Kept lower than the presets', since a
+/// branch-heavy random production can blow up fast.
+const RANDOM_GENERATIONS: u8 = 4;
 
-fn main() {
-	eprintln!("select a preset by pressing its character:");
-	eprintln!("\t[h] Heighway Dragon");
-	eprintln!("\t[p] Plant");
-	eprintln!("\t[t] Tree");
-	let mut input_buffer = String::new();
-	let stdin = io::stdin();
-	let selection;
+/// Generates a random production over `F X Y + - [ ]`, re-rolling until `is_valid_production`
+/// accepts it.
+fn random_production(rng: &mut impl Rng) -> String {
+	const ALPHABET: [char; 7] = ['F', 'X', 'Y', '+', '-', '[', ']'];
 	loop {
-		stdin.read_line(&mut input_buffer).unwrap();
-		selection = match input_buffer.trim() {
-			// TODO: implement custom input
-			"h" => HeighwayDragon,
-			"p" => Plant,
-			"t" => Tree,
-			_ => {
-				eprintln!("unrecognized. reinput:");
-				input_buffer.clear();
-				continue;
+		let length = rng.gen_range(4..12);
+		let candidate: String = (0..length).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())]).collect();
+		if is_valid_production(&candidate) {
+			return candidate;
+		}
+	}
+}
+
+/// A candidate production is rejected if it's empty, cancels a turn immediately (`+-` or `-+`),
+/// never turns at all, never draws anything (no `F`), or its `[`/`]` brackets are unbalanced.
+fn is_valid_production(production: &str) -> bool {
+	if production.is_empty() {
+		return false;
+	}
+	if production.contains("+-") || production.contains("-+") {
+		return false;
+	}
+	if !production.contains('+') && !production.contains('-') {
+		return false;
+	}
+	if !production.contains('F') {
+		return false;
+	}
+	let mut depth: i32 = 0;
+	for c in production.chars() {
+		match c {
+			'[' => depth += 1,
+			']' => {
+				depth -= 1;
+				if depth < 0 {
+					return false;
+				}
+			},
+			_ => {},
+		}
+	}
+	depth == 0
+}
+
+/// Scales each symbol's production weights so they sum to 1.0, so callers can supply e.g. `3.0`
+/// and `1.0` instead of doing the division themselves.
+fn normalize_weights(rules: &mut HashMap<char, Vec<(String, f32)>>) {
+	for productions in rules.values_mut() {
+		let total: f32 = productions.iter().map(|(_, weight)| weight).sum();
+		if total > 0.0 {
+			for (_, weight) in productions.iter_mut() {
+				*weight /= total;
 			}
-		};
-		break;
+		}
+	}
+}
+
+/// Draws one production for a symbol at random, weighted by each production's probability.
+fn choose_production<'a>(productions: &'a [(String, f32)], rng: &mut impl Rng) -> &'a str {
+	let mut roll = rng.gen::<f32>();
+	for (production, weight) in productions {
+		if roll < *weight {
+			return production;
+		}
+		roll -= weight;
+	}
+	// floating-point drift can leave a tiny remainder; fall back to the last production.
+	&productions.last().expect("a symbol must have at least one production").0
+}
+
+/// Reads a custom definition from `file_path` if given, otherwise from stdin (terminated by a
+/// blank line).
+fn read_custom_definition(file_path: Option<&str>) -> CustomDefinition {
+	match file_path {
+		Some(path) => {
+			let file = File::open(path).expect("could not open custom definition file");
+			parse_custom_definition(BufReader::new(file))
+		},
+		None => {
+			eprintln!("enter a custom L-system definition, then an empty line to finish:");
+			let mut lines = Vec::new();
+			for line in io::stdin().lock().lines() {
+				let line = line.unwrap();
+				if line.trim().is_empty() {
+					break;
+				}
+				lines.push(line);
+			}
+			parse_custom_definition(Cursor::new(lines.join("\n")))
+		},
+	}
+}
+
+/// Parses a line-based L-system definition:
+/// `axiom: F`, `angle: 90`, `generations: 5`, `F = F+G`, `F -> forward`.
+/// A rule's symbol may repeat across several lines to make it stochastic; a trailing number on
+/// the production line is its weight (default 1.0), and weights are normalized afterward.
+/// Symbols used in the axiom or a production default to `Action::None` if never mapped, and to
+/// the identity rule if never given one.
+/// The stroke ramp can be overridden with `trunk_color: 101,67,33`, `leaf_color: 34,139,34`,
+/// `trunk_width: 5`, and `leaf_width: 1`; any left unspecified fall back to the plant defaults.
+fn parse_custom_definition(reader: impl BufRead) -> CustomDefinition {
+	let mut axiom = String::new();
+	let mut angle_delta = 0.0;
+	let mut generations = None;
+	let mut actions = HashMap::new();
+	let mut rules: HashMap<char, Vec<(String, f32)>> = HashMap::new();
+	let mut stroke = StrokeStyle::default_plant();
+
+	for line in reader.lines() {
+		let line = line.unwrap();
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		if let Some(value) = line.strip_prefix("axiom:") {
+			axiom = value.trim().to_string();
+		} else if let Some(value) = line.strip_prefix("angle:") {
+			let degrees: f32 = value.trim().parse().expect("angle must be a number");
+			angle_delta = degrees.to_radians();
+		} else if let Some(value) = line.strip_prefix("generations:") {
+			generations = Some(value.trim().parse().expect("generations must be a whole number"));
+		} else if let Some(value) = line.strip_prefix("trunk_color:") {
+			stroke.trunk_color = parse_color(value.trim());
+		} else if let Some(value) = line.strip_prefix("leaf_color:") {
+			stroke.leaf_color = parse_color(value.trim());
+		} else if let Some(value) = line.strip_prefix("trunk_width:") {
+			stroke.trunk_width = value.trim().parse().expect("trunk_width must be a number");
+		} else if let Some(value) = line.strip_prefix("leaf_width:") {
+			stroke.leaf_width = value.trim().parse().expect("leaf_width must be a number");
+		} else {
+			let symbol_len = line.chars().next().expect("a definition line needs a symbol").len_utf8();
+			let symbol = parse_symbol(&line[..symbol_len]);
+			let rest = line[symbol_len..].trim_start();
+			if let Some(name) = rest.strip_prefix("->") {
+				actions.insert(symbol, action_from_name(name.trim()));
+			} else if let Some(production) = rest.strip_prefix('=') {
+				let (production, weight) = parse_production(production.trim());
+				rules.entry(symbol).or_default().push((production, weight));
+			} else {
+				panic!("could not parse definition line: `{line}`");
+			}
+		}
+	}
+
+	fill_in_defaults(&axiom, &mut actions, &mut rules);
+	normalize_weights(&mut rules);
+
+	CustomDefinition {
+		actions,
+		angle_delta,
+		axiom,
+		generations,
+		rules,
+		stroke,
+	}
+}
+
+/// Parses a comma-separated `r,g,b` color, e.g. `101,67,33`.
+fn parse_color(text: &str) -> Color {
+	let mut channels = text.split(',').map(|channel| channel.trim().parse().expect("color channel must be 0-255"));
+	let color = (
+		channels.next().expect("color needs a red channel"),
+		channels.next().expect("color needs a green channel"),
+		channels.next().expect("color needs a blue channel"),
+	);
+	assert!(channels.next().is_none(), "`{text}` is not an r,g,b color");
+	color
+}
+
+/// Parses the single symbol on the left-hand side of a `->` or `=` definition line.
+fn parse_symbol(text: &str) -> char {
+	let text = text.trim();
+	let mut chars = text.chars();
+	let symbol = chars.next().expect("a definition line needs a symbol");
+	assert!(chars.next().is_none(), "`{text}` is not a single symbol");
+	symbol
+}
+
+/// Splits a production's trailing weight off, if present, defaulting to 1.0.
+fn parse_production(text: &str) -> (String, f32) {
+	match text.rsplit_once(char::is_whitespace) {
+		Some((production, weight)) if weight.parse::<f32>().is_ok() => {
+			(production.to_string(), weight.parse().unwrap())
+		},
+		_ => (text.to_string(), 1.0),
 	}
-	let generations = LindenmeyerSystem::recommended_generations(selection);
-	let system = LindenmeyerSystem::from_preset(selection);
+}
+
+/// Maps an action name used in a custom definition to its `Action` variant.
+fn action_from_name(name: &str) -> Action {
+	match name {
+		"forward" => Action::Forward,
+		"turn_left" => Action::IncrementAngle,
+		"turn_right" => Action::DecrementAngle,
+		"pitch_up" => Action::PitchUp,
+		"pitch_down" => Action::PitchDown,
+		"roll_left" => Action::RollLeft,
+		"roll_right" => Action::RollRight,
+		"push" => Action::Push,
+		"pop" => Action::Pop,
+		"none" => Action::None,
+		other => panic!("unrecognized action `{other}`"),
+	}
+}
+
+/// Any symbol seen in the axiom or a production that wasn't given an explicit action defaults to
+/// `Action::None`; any symbol without an explicit rule maps to itself.
+fn fill_in_defaults(axiom: &str, actions: &mut HashMap<char, Action>, rules: &mut HashMap<char, Vec<(String, f32)>>) {
+	let mut symbols: HashSet<char> = axiom.chars().collect();
+	symbols.extend(rules.keys());
+	for productions in rules.values() {
+		for (production, _) in productions {
+			symbols.extend(production.chars());
+		}
+	}
+	for symbol in symbols {
+		actions.entry(symbol).or_insert(Action::None);
+		rules.entry(symbol).or_insert_with(|| vec![(symbol.to_string(), 1.0)]);
+	}
+}
+
+
+fn main() {
+	let args = parse_args();
+
+	// an explicit seed makes a run reproducible; otherwise draw one from the OS.
+	let mut rng = match args.seed {
+		Some(seed) => StdRng::seed_from_u64(seed),
+		None => StdRng::from_entropy(),
+	};
+
+	let (generations, system) = if args.random {
+		let system = LindenmeyerSystem::from_random(&mut rng);
+		print_grammar(&system);
+		(RANDOM_GENERATIONS, system)
+	} else {
+		eprintln!("select a preset by pressing its character:");
+		eprintln!("\t[h] Heighway Dragon");
+		eprintln!("\t[p] Plant");
+		eprintln!("\t[t] Tree");
+		eprintln!("\t[c] Custom");
+		let mut input_buffer = String::new();
+		let stdin = io::stdin();
+		let selection;
+		loop {
+			stdin.read_line(&mut input_buffer).unwrap();
+			selection = match input_buffer.trim() {
+				"h" => Selection::Preset(HeighwayDragon),
+				"p" => Selection::Preset(Plant),
+				"t" => Selection::Preset(Tree),
+				"c" => Selection::Custom,
+				_ => {
+					eprintln!("unrecognized. reinput:");
+					input_buffer.clear();
+					continue;
+				}
+			};
+			break;
+		}
+		match selection {
+			Selection::Custom => {
+				let definition = read_custom_definition(args.file_path.as_deref());
+				(definition.generations.unwrap_or(5), LindenmeyerSystem::from_custom(definition))
+			},
+			Selection::Preset(preset) => (
+				LindenmeyerSystem::recommended_generations(preset),
+				LindenmeyerSystem::from_preset(preset),
+			),
+		}
+	};
 
 	let angle_delta = system.angle_delta;
-	let mut angle = 0.0;
 	let mut string = system.axiom;
-	let mut data = Data::new().move_to((0, 0));
-	let mut position = (0, 0);
-	let mut stack = Vec::new();
 
 	for _ in 0..generations {
 		let mut string_new = String::new();
 		for c in string.chars() {
-			let replacement = &system.rules[&c];
-			string_new.push_str(replacement);
+			let production = choose_production(&system.rules[&c], &mut rng);
+			string_new.push_str(production);
 		}
 		string = string_new;
 	}
 
+	// walk once at a unit step to measure the raw geometry, then rescale so it fills the canvas.
+	let probe = interpret(&system.actions, &string, angle_delta, 1.0);
+	let (min, max) = bounding_box(&probe);
+	let raw_size = (max.0 - min.0).max(max.1 - min.1).max(f32::EPSILON);
+	let step = (CANVAS_SIZE - 2.0 * MARGIN) / raw_size;
+
+	let events = interpret(&system.actions, &string, angle_delta, step);
+
+	match args.format {
+		OutputFormat::Svg => svg::write(io::stdout(), &write_svg(&events, &system.stroke)).unwrap(),
+		OutputFormat::Obj => print!("{}", write_obj(&events)),
+	}
+}
+
+/// Prints a random grammar's axiom and rules to stderr so an interesting result can be
+/// reproduced (together with the `--seed` that produced it).
+fn print_grammar(system: &LindenmeyerSystem) {
+	eprintln!("axiom: {}", system.axiom);
+	let mut symbols: Vec<&char> = system.rules.keys().collect();
+	symbols.sort();
+	for symbol in symbols {
+		for (production, weight) in &system.rules[symbol] {
+			eprintln!("{symbol} = {production} {weight:.2}");
+		}
+	}
+}
+
+/// The figure is scaled so the longer side of the rendered canvas is `CANVAS_SIZE`, with `MARGIN`
+/// of blank space kept around the figure on every side.
+const CANVAS_SIZE: f32 = 2000.0;
+const MARGIN: f32 = 100.0;
+
+/// The smallest axis-aligned box, in the XY plane, containing every point the turtle visited.
+fn bounding_box(events: &[TurtleEvent]) -> ((f32, f32), (f32, f32)) {
+	let mut min = (f32::INFINITY, f32::INFINITY);
+	let mut max = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+	for event in events {
+		let (x, y, _) = match *event {
+			TurtleEvent::MoveTo(position) | TurtleEvent::LineTo(position, _) => position,
+		};
+		min = (min.0.min(x), min.1.min(y));
+		max = (max.0.max(x), max.1.max(y));
+	}
+	(min, max)
+}
+
+/// Walks a generated string with a 3D turtle, returning its path as a sequence of moves.
+fn interpret(actions: &HashMap<char, Action>, string: &str, angle_delta: f32, step: f32) -> Vec<TurtleEvent> {
+	let mut frame = Frame::identity();
+	let mut position: Vector3 = (0.0, 0.0, 0.0);
+	let mut depth: u32 = 0;
+	let mut stack: Vec<(Vector3, Frame)> = Vec::new();
+	let mut events = vec![TurtleEvent::MoveTo(position)];
+
 	for c in string.chars() {
-		match system.actions[&c] {
-			Action::DecrementAngle => angle -= angle_delta,
+		match actions[&c] {
+			Action::DecrementAngle => frame.yaw(-angle_delta),
 			Action::Forward => {
-				let (new_x, new_y) = get_vector(angle, 10);
-				let (old_x, old_y) = position;
-				position = (old_x + new_x, old_y + new_y);
-				data = data.line_by((new_x, new_y));
+				position = add(position, scale(frame.heading, step));
+				events.push(TurtleEvent::LineTo(position, depth));
 			},
-			Action::IncrementAngle => angle += angle_delta,
+			Action::IncrementAngle => frame.yaw(angle_delta),
 			Action::None => {},
+			Action::PitchDown => frame.pitch(-angle_delta),
+			Action::PitchUp => frame.pitch(angle_delta),
 			Action::Pop => {
-				(position, angle) = stack.pop().expect("malformed system");
-				data = data.move_to(position);
+				(position, frame) = stack.pop().expect("malformed system");
+				depth -= 1;
+				events.push(TurtleEvent::MoveTo(position));
+			},
+			Action::Push => {
+				stack.push((position, frame));
+				depth += 1;
 			},
-			Action::Push => stack.push((position, angle)),
+			Action::RollLeft => frame.roll(-angle_delta),
+			Action::RollRight => frame.roll(angle_delta),
 		}
 	}
+	events
+}
 
-	let path = Path::new()
-		.set("fill", "none")
-		.set("stroke", "black")
-		.set("stroke-width", 3)
-		.set("d", data);
+/// Projects the turtle's path onto the XY plane and renders it as SVG. Since a single `<path>`
+/// can't vary stroke attributes within itself, each run of same-depth `Forward` segments becomes
+/// its own `Path`, colored and widthed by `stroke`'s depth ramp. The viewBox is fit to the actual
+/// geometry (plus `MARGIN`) so the figure is centered and fully visible regardless of its size.
+fn write_svg(events: &[TurtleEvent], stroke: &StrokeStyle) -> Document {
+	let (min, max) = bounding_box(events);
+	let view_box = (
+		min.0 - MARGIN,
+		min.1 - MARGIN,
+		(max.0 - min.0) + 2.0 * MARGIN,
+		(max.1 - min.1) + 2.0 * MARGIN,
+	);
+	let mut document = Document::new().set("viewBox", view_box);
+	let mut position = (0, 0);
+	let mut run: Option<(u32, Data)> = None;
 
-	let document = Document::new()
-		.set("viewBox", (-1000, -1000, 2000, 2000))
-		.add(path);
+	for event in events {
+		match *event {
+			TurtleEvent::MoveTo((x, y, _)) => {
+				document = flush_run(document, run.take(), stroke);
+				position = (x as isize, y as isize);
+			},
+			TurtleEvent::LineTo((x, y, _), depth) => {
+				if run.as_ref().is_some_and(|(run_depth, _)| *run_depth != depth) {
+					document = flush_run(document, run.take(), stroke);
+				}
+				run.get_or_insert_with(|| (depth, Data::new().move_to(position)));
+				position = (x as isize, y as isize);
+				run = run.map(|(depth, data)| (depth, data.line_to(position)));
+			},
+		}
+	}
+	flush_run(document, run, stroke)
+}
+
+/// Adds the in-progress run as a `Path` to `document`, if there was one.
+fn flush_run(document: Document, run: Option<(u32, Data)>, stroke: &StrokeStyle) -> Document {
+	match run {
+		Some((depth, data)) => document.add(
+			Path::new()
+				.set("fill", "none")
+				.set("stroke", to_css_color(stroke.color_at(depth)))
+				.set("stroke-width", stroke.width_at(depth))
+				.set("d", data),
+		),
+		None => document,
+	}
+}
+
+fn to_css_color((r, g, b): Color) -> String {
+	format!("rgb({r}, {g}, {b})")
+}
 
-	// this program writes the svg straight to standard output. redirect!
-	let stdout = io::stdout();
-	svg::write(stdout, &document).unwrap();
+/// Renders the turtle's path as a Wavefront .obj: one vertex per move, one line element per
+/// `Forward` segment.
+fn write_obj(events: &[TurtleEvent]) -> String {
+	let mut obj = String::new();
+	for event in events {
+		let (x, y, z) = match *event {
+			TurtleEvent::MoveTo(position) | TurtleEvent::LineTo(position, _) => position,
+		};
+		obj.push_str(&format!("v {x} {y} {z}\n"));
+	}
+	// .obj vertex indices are 1-based.
+	let mut previous = None;
+	for (i, event) in events.iter().enumerate() {
+		let index = i + 1;
+		match event {
+			TurtleEvent::MoveTo(_) => previous = Some(index),
+			TurtleEvent::LineTo(_, _) => {
+				if let Some(previous_index) = previous {
+					obj.push_str(&format!("l {previous_index} {index}\n"));
+				}
+				previous = Some(index);
+			},
+		}
+	}
+	obj
 }
 
-fn get_vector(angle: f32, length: isize) -> (isize, isize) {
-	let length = length as f32;
-	((length * angle.cos()) as isize, (length * angle.sin()) as isize)
+struct Args {
+	seed: Option<u64>,
+	file_path: Option<String>,
+	format: OutputFormat,
+	random: bool,
+}
+
+/// Scans CLI arguments for `--seed <u64>`, `--file <path>`, `--format <svg|obj>`, and `--random`,
+/// all optional.
+fn parse_args() -> Args {
+	let mut args = Args {
+		seed: None,
+		file_path: None,
+		format: OutputFormat::Svg,
+		random: false,
+	};
+	let mut raw_args = std::env::args().skip(1);
+	while let Some(arg) = raw_args.next() {
+		match arg.as_str() {
+			"--seed" => args.seed = raw_args.next().and_then(|value| value.parse().ok()),
+			"--file" => args.file_path = raw_args.next(),
+			"--format" => args.format = match raw_args.next().as_deref() {
+				Some("obj") => OutputFormat::Obj,
+				Some("svg") | None => OutputFormat::Svg,
+				Some(other) => panic!("unrecognized format `{other}`"),
+			},
+			"--random" => args.random = true,
+			_ => {},
+		}
+	}
+	args
 }